@@ -0,0 +1,561 @@
+use crate::error::{Error, ErrorCode};
+use crate::payload::PayloadFormat;
+use serde::de::{
+    self, DeserializeSeed, EnumAccess, IntoDeserializer, MapAccess, SeqAccess, VariantAccess,
+    Visitor,
+};
+
+/// A deserializer that reconstructs an enum or struct from its variant name
+///
+/// Only unit variants and unit structs can be deserialized, since any data
+/// they might have held was discarded during serialization
+pub struct Deserializer<'de> {
+    pub(crate) input: &'de str,
+    case_insensitive: bool,
+}
+
+impl<'de> Deserializer<'de> {
+    /// Create a deserializer that reads a variant/struct name from `input`
+    ///
+    /// Matching is strict: case- and whitespace-sensitive. Use
+    /// [`crate::Options`] to configure a more forgiving deserializer
+    pub fn new(input: &'de str) -> Self {
+        Deserializer {
+            input,
+            case_insensitive: false,
+        }
+    }
+
+    pub(crate) fn with_options(input: &'de str, case_insensitive: bool) -> Self {
+        Deserializer {
+            input,
+            case_insensitive,
+        }
+    }
+
+    /// Checks whether `name` matches the start of `input`, honoring
+    /// `case_insensitive`
+    fn matches(&self, name: &str) -> bool {
+        if self.case_insensitive {
+            self.input
+                .get(..name.len())
+                .is_some_and(|prefix| prefix.eq_ignore_ascii_case(name))
+        } else {
+            self.input.starts_with(name)
+        }
+    }
+}
+
+impl<'de> de::Deserializer<'de> for &mut Deserializer<'de> {
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::deserialization(ErrorCode::UnsupportedOperation(
+            "any".to_string(),
+        )))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes
+        byte_buf option unit seq tuple map identifier ignored_any
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.matches(name) {
+            self.input = &self.input[name.len()..];
+            visitor.visit_unit()
+        } else {
+            Err(Error::deserialization(ErrorCode::InvalidVariantName {
+                received: self.input.to_string(),
+                allowed: vec![name.to_string()],
+            }))
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::deserialization(ErrorCode::UnsupportedOperation(
+            format!("newtype struct {}", name),
+        )))
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        _len: usize,
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::deserialization(ErrorCode::UnsupportedOperation(
+            format!("tuple struct {}", name),
+        )))
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::deserialization(ErrorCode::UnsupportedOperation(
+            format!("struct {}", name),
+        )))
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match variants.iter().find(|variant| self.matches(variant)) {
+            Some(variant) => {
+                self.input = &self.input[variant.len()..];
+                visitor.visit_enum(VariantDeserializer { variant })
+            }
+            None => Err(de::Error::unknown_variant(self.input, variants)),
+        }
+    }
+}
+
+struct VariantDeserializer {
+    variant: &'static str,
+}
+
+impl<'de> EnumAccess<'de> for VariantDeserializer {
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((value, self))
+    }
+}
+
+impl<'de> VariantAccess<'de> for VariantDeserializer {
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        Ok(())
+    }
+
+    fn newtype_variant_seed<T>(self, _seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        Err(Error::deserialization(ErrorCode::UnsupportedOperation(
+            format!("newtype variant {}", self.variant),
+        )))
+    }
+
+    fn tuple_variant<V>(self, _len: usize, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::deserialization(ErrorCode::UnsupportedOperation(
+            format!("tuple variant {}", self.variant),
+        )))
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        _visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::deserialization(ErrorCode::UnsupportedOperation(
+            format!("struct variant {}", self.variant),
+        )))
+    }
+}
+
+/// A deserializer that reconstructs an enum or struct from a `"Name(payload)"`
+/// string, delegating the payload to a [`PayloadFormat`]
+///
+/// Unlike [`Deserializer`], data-bearing variants/structs round-trip: the
+/// text between the parentheses is handed to `inner` for decoding. Unit
+/// variants/structs keep matching the bare name, with no parentheses expected
+pub struct PayloadDeserializer<'de, 'a, F> {
+    name: &'de str,
+    payload: Option<&'de str>,
+    inner: &'a F,
+}
+
+impl<'de, 'a, F> PayloadDeserializer<'de, 'a, F>
+where
+    F: PayloadFormat,
+{
+    pub(crate) fn new(input: &'de str, inner: &'a F) -> crate::Result<Self> {
+        match input.find('(') {
+            Some(at) => match input.rfind(')') {
+                Some(close) if close == input.len() - 1 => Ok(PayloadDeserializer {
+                    name: &input[..at],
+                    payload: Some(&input[at + 1..close]),
+                    inner,
+                }),
+                Some(close) => Err(Error::deserialization_at(
+                    ErrorCode::TrailingCharacters {
+                        at: close + 1,
+                        rest: input[close + 1..].to_string(),
+                    },
+                    close + 1,
+                )),
+                None => Err(Error::deserialization(ErrorCode::UnsupportedOperation(
+                    format!("missing closing ')' in {:?}", input),
+                ))),
+            },
+            None => Ok(PayloadDeserializer {
+                name: input,
+                payload: None,
+                inner,
+            }),
+        }
+    }
+}
+
+fn missing_payload(name: &str) -> Error {
+    Error::deserialization(ErrorCode::UnsupportedOperation(format!(
+        "missing payload for {}",
+        name
+    )))
+}
+
+/// Adapts a [`Visitor`] into a [`DeserializeSeed`] so a newtype's payload can
+/// be decoded the same way as any other seed-driven value
+struct NewtypeStructSeed<V>(V);
+
+impl<'de, V> DeserializeSeed<'de> for NewtypeStructSeed<V>
+where
+    V: Visitor<'de>,
+{
+    type Value = V::Value;
+
+    fn deserialize<D>(self, deserializer: D) -> Result<Self::Value, D::Error>
+    where
+        D: de::Deserializer<'de>,
+    {
+        self.0.visit_newtype_struct(deserializer)
+    }
+}
+
+/// Splits a tuple struct/variant's comma-joined fields back apart
+///
+/// This is the other half of the `,`-joining done on the serialization side;
+/// a field whose encoded form contains a `,` is rejected there, so it never
+/// reaches this split
+fn split_fields(payload: &str) -> Vec<&str> {
+    if payload.is_empty() {
+        Vec::new()
+    } else {
+        payload.split(',').collect()
+    }
+}
+
+/// Splits a struct/struct variant's `key=value,...` fields back into pairs
+///
+/// Like [`split_fields`], a field whose encoded form contains a `,` or `=`
+/// is rejected on the serialization side, so it never reaches this split
+fn split_pairs(payload: &str) -> Vec<(&str, &str)> {
+    if payload.is_empty() {
+        return Vec::new();
+    }
+
+    payload
+        .split(',')
+        .map(|pair| {
+            let mut parts = pair.splitn(2, '=');
+            let key = parts.next().unwrap_or_default();
+            let value = parts.next().unwrap_or_default();
+            (key, value)
+        })
+        .collect()
+}
+
+struct PayloadSeqAccess<'de, 'a, F> {
+    fields: std::vec::IntoIter<&'de str>,
+    inner: &'a F,
+}
+
+impl<'de, 'a, F> SeqAccess<'de> for PayloadSeqAccess<'de, 'a, F>
+where
+    F: PayloadFormat,
+{
+    type Error = Error;
+
+    fn next_element_seed<T>(&mut self, seed: T) -> Result<Option<T::Value>, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        match self.fields.next() {
+            Some(field) => self.inner.decode(field, seed).map(Some),
+            None => Ok(None),
+        }
+    }
+}
+
+struct PayloadMapAccess<'de, 'a, F> {
+    pairs: std::vec::IntoIter<(&'de str, &'de str)>,
+    inner: &'a F,
+    value: Option<&'de str>,
+}
+
+impl<'de, 'a, F> MapAccess<'de> for PayloadMapAccess<'de, 'a, F>
+where
+    F: PayloadFormat,
+{
+    type Error = Error;
+
+    fn next_key_seed<K>(&mut self, seed: K) -> Result<Option<K::Value>, Self::Error>
+    where
+        K: DeserializeSeed<'de>,
+    {
+        match self.pairs.next() {
+            Some((key, value)) => {
+                self.value = Some(value);
+                seed.deserialize(key.into_deserializer()).map(Some)
+            }
+            None => Ok(None),
+        }
+    }
+
+    fn next_value_seed<V>(&mut self, seed: V) -> Result<V::Value, Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = self.value.take().unwrap_or_default();
+        self.inner.decode(value, seed)
+    }
+}
+
+impl<'de, 'a, F> de::Deserializer<'de> for PayloadDeserializer<'de, 'a, F>
+where
+    F: PayloadFormat,
+{
+    type Error = Error;
+
+    fn deserialize_any<V>(self, _visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        Err(Error::deserialization(ErrorCode::UnsupportedOperation(
+            "any".to_string(),
+        )))
+    }
+
+    serde::forward_to_deserialize_any! {
+        bool i8 i16 i32 i64 u8 u16 u32 u64 f32 f64 char str string bytes
+        byte_buf option unit seq tuple map identifier ignored_any
+    }
+
+    fn deserialize_unit_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.name != name {
+            return Err(Error::deserialization(ErrorCode::InvalidVariantName {
+                received: self.name.to_string(),
+                allowed: vec![name.to_string()],
+            }));
+        }
+
+        match self.payload {
+            None => visitor.visit_unit(),
+            Some(_) => Err(Error::deserialization(ErrorCode::UnsupportedOperation(
+                format!("unexpected payload for unit struct {}", name),
+            ))),
+        }
+    }
+
+    fn deserialize_newtype_struct<V>(
+        self,
+        name: &'static str,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.name != name {
+            return Err(Error::deserialization(ErrorCode::InvalidVariantName {
+                received: self.name.to_string(),
+                allowed: vec![name.to_string()],
+            }));
+        }
+
+        let payload = self.payload.ok_or_else(|| missing_payload(name))?;
+        self.inner.decode(payload, NewtypeStructSeed(visitor))
+    }
+
+    fn deserialize_tuple_struct<V>(
+        self,
+        name: &'static str,
+        _len: usize,
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.name != name {
+            return Err(Error::deserialization(ErrorCode::InvalidVariantName {
+                received: self.name.to_string(),
+                allowed: vec![name.to_string()],
+            }));
+        }
+
+        let payload = self.payload.ok_or_else(|| missing_payload(name))?;
+        visitor.visit_seq(PayloadSeqAccess {
+            fields: split_fields(payload).into_iter(),
+            inner: self.inner,
+        })
+    }
+
+    fn deserialize_struct<V>(
+        self,
+        name: &'static str,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        if self.name != name {
+            return Err(Error::deserialization(ErrorCode::InvalidVariantName {
+                received: self.name.to_string(),
+                allowed: vec![name.to_string()],
+            }));
+        }
+
+        let payload = self.payload.ok_or_else(|| missing_payload(name))?;
+        visitor.visit_map(PayloadMapAccess {
+            pairs: split_pairs(payload).into_iter(),
+            inner: self.inner,
+            value: None,
+        })
+    }
+
+    fn deserialize_enum<V>(
+        self,
+        _name: &'static str,
+        variants: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        match variants.iter().find(|&&variant| variant == self.name) {
+            Some(&variant) => visitor.visit_enum(PayloadVariantAccess {
+                variant,
+                payload: self.payload,
+                inner: self.inner,
+            }),
+            None => Err(de::Error::unknown_variant(self.name, variants)),
+        }
+    }
+}
+
+struct PayloadVariantAccess<'de, 'a, F> {
+    variant: &'static str,
+    payload: Option<&'de str>,
+    inner: &'a F,
+}
+
+impl<'de, 'a, F> EnumAccess<'de> for PayloadVariantAccess<'de, 'a, F>
+where
+    F: PayloadFormat,
+{
+    type Error = Error;
+    type Variant = Self;
+
+    fn variant_seed<V>(self, seed: V) -> Result<(V::Value, Self::Variant), Self::Error>
+    where
+        V: DeserializeSeed<'de>,
+    {
+        let value = seed.deserialize(self.variant.into_deserializer())?;
+        Ok((value, self))
+    }
+}
+
+impl<'de, 'a, F> VariantAccess<'de> for PayloadVariantAccess<'de, 'a, F>
+where
+    F: PayloadFormat,
+{
+    type Error = Error;
+
+    fn unit_variant(self) -> Result<(), Self::Error> {
+        match self.payload {
+            None => Ok(()),
+            Some(_) => Err(Error::deserialization(ErrorCode::UnsupportedOperation(
+                format!("unexpected payload for unit variant {}", self.variant),
+            ))),
+        }
+    }
+
+    fn newtype_variant_seed<T>(self, seed: T) -> Result<T::Value, Self::Error>
+    where
+        T: DeserializeSeed<'de>,
+    {
+        let payload = self
+            .payload
+            .ok_or_else(|| missing_payload(self.variant))?;
+        self.inner.decode(payload, seed)
+    }
+
+    fn tuple_variant<V>(self, _len: usize, visitor: V) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let payload = self.payload.ok_or_else(|| missing_payload(self.variant))?;
+        visitor.visit_seq(PayloadSeqAccess {
+            fields: split_fields(payload).into_iter(),
+            inner: self.inner,
+        })
+    }
+
+    fn struct_variant<V>(
+        self,
+        _fields: &'static [&'static str],
+        visitor: V,
+    ) -> Result<V::Value, Self::Error>
+    where
+        V: Visitor<'de>,
+    {
+        let payload = self.payload.ok_or_else(|| missing_payload(self.variant))?;
+        visitor.visit_map(PayloadMapAccess {
+            pairs: split_pairs(payload).into_iter(),
+            inner: self.inner,
+            value: None,
+        })
+    }
+}
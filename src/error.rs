@@ -12,6 +12,7 @@ pub type Result<T> = result::Result<T, Error>;
 pub struct Error {
     direction: Direction,
     code: ErrorCode,
+    position: Option<usize>,
 }
 
 impl Error {
@@ -19,6 +20,15 @@ impl Error {
         Self {
             direction: Direction::Deserialization,
             code,
+            position: None,
+        }
+    }
+
+    pub(crate) fn deserialization_at(code: ErrorCode, position: usize) -> Self {
+        Self {
+            direction: Direction::Deserialization,
+            code,
+            position: Some(position),
         }
     }
 
@@ -26,8 +36,20 @@ impl Error {
         Self {
             direction: Direction::Serialization,
             code,
+            position: None,
         }
     }
+
+    /// The byte offset into the input at which this error occured, if known
+    pub fn position(&self) -> Option<usize> {
+        self.position
+    }
+
+    /// Whether this is an [`ErrorCode::Inapplicable`] error, i.e. the value
+    /// simply had no name to give rather than having failed to be serialized
+    pub(crate) fn is_inapplicable(&self) -> bool {
+        matches!(self.code, ErrorCode::Inapplicable)
+    }
 }
 
 #[derive(Clone, PartialEq, Eq, Debug)]
@@ -57,7 +79,9 @@ pub(crate) enum ErrorCode {
         received: String,
         allowed: Vec<String>,
     },
-    TrailingCharacters,
+    TrailingCharacters { at: usize, rest: String },
+    Io(String),
+    Inapplicable,
 }
 
 impl fmt::Display for ErrorCode {
@@ -78,10 +102,11 @@ impl fmt::Display for ErrorCode {
                     received, allowed
                 )
             }
-            ErrorCode::TrailingCharacters => write!(
-                f,
-                "trailing characters: input ends with trailing characters"
-            ),
+            ErrorCode::TrailingCharacters { at, rest } => {
+                write!(f, "trailing characters at byte {}: {:?}", at, rest)
+            }
+            ErrorCode::Io(msg) => write!(f, "io error: {}", msg),
+            ErrorCode::Inapplicable => f.write_str("value has no serde name"),
         }
     }
 }
@@ -104,6 +129,7 @@ impl de::Error for Error {
         Error {
             direction: Direction::Deserialization,
             code: ErrorCode::Message(format!("{}", msg)),
+            position: None,
         }
     }
 
@@ -115,6 +141,7 @@ impl de::Error for Error {
                 received: variant.to_string(),
                 allowed: expected.iter().map(|v| v.to_string()).collect(),
             },
+            position: None,
         }
     }
 
@@ -126,6 +153,7 @@ impl de::Error for Error {
                 unexpected: format!("{}", unexp),
                 expected: format!("{}", exp),
             },
+            position: None,
         }
     }
 }
@@ -136,6 +164,7 @@ impl ser::Error for Error {
         Error {
             direction: Direction::Serialization,
             code: ErrorCode::Message(format!("{}", msg)),
+            position: None,
         }
     }
 }
@@ -57,14 +57,20 @@
 
 mod de;
 mod error;
+mod options;
+mod payload;
 mod ser;
 
+use std::io;
+
 use serde::Deserialize;
 use serde::Serialize;
 
 pub use de::Deserializer;
 pub(crate) use error::ErrorCode;
 pub use error::{Error, Result};
+pub use options::Options;
+pub use payload::PayloadFormat;
 pub use ser::Serializer;
 
 /// Convert enums and structs into its variant name
@@ -106,6 +112,34 @@ where
     value.serialize(&mut serializer)
 }
 
+/// Write the variant/struct name of a value straight into an `io::Write`
+///
+/// Behaves exactly like [`to_str`], but writes the resolved name into
+/// `writer` instead of returning it, so callers streaming a type name into
+/// a log sink or socket don't need the intermediate allocation
+///
+/// ```rust
+///use serde::{Serialize, Deserialize};
+///use serde_typename::to_writer;
+///
+///#[derive(Debug, PartialEq, Serialize, Deserialize)]
+///enum Enum {
+///    NoData,
+///    WithData(u8),
+///}
+///
+///let mut buf = Vec::new();
+///to_writer(&mut buf, &Enum::WithData(1)).unwrap();
+///assert_eq!(buf, b"WithData");
+/// ```
+pub fn to_writer<W, T>(mut writer: W, value: &T) -> Result<()>
+where
+    W: io::Write,
+    T: Serialize,
+{
+    ser::write_name(&mut writer, to_str(value)?)
+}
+
 /// Convert a variant name back into an enum or struct if possible
 ///
 /// Keep in mind that all target variants or structs which
@@ -143,15 +177,147 @@ pub fn from_str<'a, D>(value: &'a str) -> Result<D>
 where
     D: Deserialize<'a>,
 {
+    let original_len = value.len();
     let mut deserializer = de::Deserializer::new(value);
     let variant = D::deserialize(&mut deserializer)?;
     if deserializer.input.is_empty() {
         Ok(variant)
     } else {
-        Err(Error::deserialization(error::ErrorCode::TrailingCharacters))
+        let at = original_len - deserializer.input.len();
+        let rest = deserializer.input.to_string();
+        Err(Error::deserialization_at(
+            ErrorCode::TrailingCharacters { at, rest },
+            at,
+        ))
     }
 }
 
+/// Convert an enum or struct into `"Name(payload)"`, delegating the payload
+/// to `inner` instead of discarding it
+///
+/// Unit variants/structs are unaffected and keep serializing to the bare
+/// name, exactly like [`to_str`]
+///
+/// ```rust
+///use serde::{Serialize, Deserialize};
+///use serde_typename::{to_str_with_payload, from_str_with_payload, PayloadFormat, Result};
+///
+///struct Json;
+///
+///impl PayloadFormat for Json {
+///    fn encode(&self, value: &dyn erased_serde::Serialize) -> Result<String> {
+///        serde_json::to_string(value).map_err(serde::ser::Error::custom)
+///    }
+///
+///    fn decode<'de, T>(&self, input: &'de str, seed: T) -> Result<T::Value>
+///    where
+///        T: serde::de::DeserializeSeed<'de>,
+///    {
+///        let mut deserializer = serde_json::Deserializer::from_str(input);
+///        seed.deserialize(&mut deserializer).map_err(serde::de::Error::custom)
+///    }
+///}
+///
+///#[derive(Debug, PartialEq, Serialize, Deserialize)]
+///enum Enum {
+///    NoData,
+///    WithData(u8),
+///}
+///
+///assert_eq!(to_str_with_payload(&Enum::NoData, &Json).unwrap(), "NoData");
+///assert_eq!(to_str_with_payload(&Enum::WithData(1), &Json).unwrap(), "WithData(1)");
+///
+///assert_eq!(
+///    from_str_with_payload::<Enum, _>("WithData(1)", &Json).unwrap(),
+///    Enum::WithData(1)
+///);
+/// ```
+pub fn to_str_with_payload<T, F>(value: &T, inner: &F) -> Result<String>
+where
+    T: Serialize,
+    F: PayloadFormat,
+{
+    let serializer = ser::PayloadSerializer::new(inner);
+    value.serialize(serializer)
+}
+
+/// Convert a `"Name(payload)"` string back into an enum or struct, decoding
+/// the payload with `inner`
+///
+/// ```rust
+///use serde::{Serialize, Deserialize};
+///use serde_typename::{to_str_with_payload, from_str_with_payload, PayloadFormat, Result};
+///
+///struct Json;
+///
+///impl PayloadFormat for Json {
+///    fn encode(&self, value: &dyn erased_serde::Serialize) -> Result<String> {
+///        serde_json::to_string(value).map_err(serde::ser::Error::custom)
+///    }
+///
+///    fn decode<'de, T>(&self, input: &'de str, seed: T) -> Result<T::Value>
+///    where
+///        T: serde::de::DeserializeSeed<'de>,
+///    {
+///        let mut deserializer = serde_json::Deserializer::from_str(input);
+///        seed.deserialize(&mut deserializer).map_err(serde::de::Error::custom)
+///    }
+///}
+///
+///#[derive(Debug, PartialEq, Serialize, Deserialize)]
+///struct Struct {
+///    field: u8,
+///}
+///
+///let encoded = to_str_with_payload(&Struct { field: 7 }, &Json).unwrap();
+///assert_eq!(from_str_with_payload::<Struct, _>(&encoded, &Json).unwrap(), Struct { field: 7 });
+/// ```
+pub fn from_str_with_payload<'a, D, F>(input: &'a str, inner: &F) -> Result<D>
+where
+    D: Deserialize<'a>,
+    F: PayloadFormat,
+{
+    let deserializer = de::PayloadDeserializer::new(input, inner)?;
+    D::deserialize(deserializer)
+}
+
+/// Resolve the serde name of a value, descending into the fields of a struct
+/// with named fields or the values of a map
+///
+/// Unit, newtype and tuple structs and enum variants resolve to their own
+/// name, like [`to_str`]. Unlike [`to_str`], a struct with named fields does
+/// not resolve to its own name; given a `struct Event { kind: Kind, id: u64 }`,
+/// `name_of` descends into `kind` and returns `Kind`'s active variant name
+///
+/// ```rust
+///use serde::Serialize;
+///use serde_typename::name_of;
+///
+///#[derive(Serialize)]
+///enum Kind {
+///    Created,
+///    Updated(u8),
+///}
+///
+///#[derive(Serialize)]
+///struct Event {
+///    kind: Kind,
+///    id: u64,
+///}
+///
+///assert_eq!(name_of(&Kind::Created).unwrap(), "Created");
+///assert_eq!(
+///    name_of(&Event { kind: Kind::Updated(1), id: 7 }).unwrap(),
+///    "Updated"
+///);
+/// ```
+pub fn name_of<T>(value: &T) -> Result<&'static str>
+where
+    T: Serialize,
+{
+    value.serialize(&mut ser::NameExtractor {})
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -258,6 +424,64 @@ mod tests {
         }
     }
 
+    mod writer {
+        use super::*;
+
+        #[test]
+        fn unit_variants() {
+            #[derive(Serialize)]
+            enum Foo {
+                Var1,
+                #[serde(rename = "VAR2")]
+                Var2,
+            }
+
+            let mut buf = Vec::new();
+            to_writer(&mut buf, &Foo::Var1).unwrap();
+            assert_eq!(buf, b"Var1");
+
+            let mut buf = Vec::new();
+            to_writer(&mut buf, &Foo::Var2).unwrap();
+            assert_eq!(buf, b"VAR2");
+        }
+
+        #[test]
+        fn newtype_variants() {
+            #[derive(Serialize)]
+            enum Foo {
+                Var1(u32),
+            }
+
+            let mut buf = Vec::new();
+            to_writer(&mut buf, &Foo::Var1(42)).unwrap();
+            assert_eq!(buf, b"Var1");
+        }
+
+        #[test]
+        fn structs() {
+            #[derive(Serialize)]
+            struct Bar {
+                field: u8,
+            }
+
+            let mut buf = Vec::new();
+            to_writer(&mut buf, &Bar { field: 0 }).unwrap();
+            assert_eq!(buf, b"Bar");
+        }
+
+        #[test]
+        fn matches_to_str() {
+            #[derive(Serialize)]
+            enum Foo {
+                Var1(u32, u32),
+            }
+
+            let mut buf = Vec::new();
+            to_writer(&mut buf, &Foo::Var1(1, 2)).unwrap();
+            assert_eq!(buf, to_str(&Foo::Var1(1, 2)).unwrap().as_bytes());
+        }
+    }
+
     mod de {
         use super::*;
 
@@ -313,6 +537,21 @@ mod tests {
                 assert_eq!(from_str::<Foo>("VAR2").unwrap(), Foo::Var2);
             }
 
+            #[test]
+            fn trailing_characters() {
+                #[derive(Debug, PartialEq, Deserialize)]
+                enum Foo {
+                    Var1,
+                }
+
+                let err = from_str::<Foo>("Var1 garbage").unwrap_err();
+                assert_eq!(err.position(), Some(4));
+                assert_eq!(
+                    err.to_string(),
+                    "deserialization: trailing characters at byte 4: \" garbage\""
+                );
+            }
+
             mod impossible {
                 use super::*;
 
@@ -446,4 +685,256 @@ mod tests {
             }
         }
     }
+
+    mod options {
+        use super::*;
+
+        #[derive(Debug, PartialEq, Deserialize)]
+        enum Foo {
+            Bar,
+        }
+
+        #[test]
+        fn strict_by_default() {
+            assert_eq!(Options::new().from_str::<Foo>("Bar").unwrap(), Foo::Bar);
+            assert!(Options::new().from_str::<Foo>("bar").is_err());
+            assert!(Options::new().from_str::<Foo>("Bar ").is_err());
+        }
+
+        #[test]
+        fn case_insensitive() {
+            let options = Options::new().case_insensitive(true);
+
+            assert_eq!(options.from_str::<Foo>("bar").unwrap(), Foo::Bar);
+            assert_eq!(options.from_str::<Foo>("BAR").unwrap(), Foo::Bar);
+            assert!(options.from_str::<Foo>("baz").is_err());
+        }
+
+        #[test]
+        fn trim_whitespace() {
+            let options = Options::new().trim_whitespace(true);
+
+            assert_eq!(options.from_str::<Foo>("  Bar  ").unwrap(), Foo::Bar);
+            assert!(options.from_str::<Foo>("  bar  ").is_err());
+        }
+
+        #[test]
+        fn trim_whitespace_reports_position_relative_to_the_original_input() {
+            let options = Options::new().trim_whitespace(true);
+
+            let err = options.from_str::<Foo>("   Bar garbage").unwrap_err();
+            assert_eq!(err.position(), Some(6));
+        }
+
+        #[test]
+        fn allow_trailing() {
+            let options = Options::new().allow_trailing(true);
+
+            assert_eq!(options.from_str::<Foo>("Bar garbage").unwrap(), Foo::Bar);
+        }
+
+        #[test]
+        fn combined() {
+            let options = Options::new()
+                .case_insensitive(true)
+                .trim_whitespace(true)
+                .allow_trailing(true);
+
+            assert_eq!(options.from_str::<Foo>("  bar garbage  ").unwrap(), Foo::Bar);
+        }
+    }
+
+    mod payload {
+        use super::*;
+
+        struct Json;
+
+        impl PayloadFormat for Json {
+            fn encode(&self, value: &dyn erased_serde::Serialize) -> Result<String> {
+                serde_json::to_string(value).map_err(serde::ser::Error::custom)
+            }
+
+            fn decode<'de, T>(&self, input: &'de str, seed: T) -> Result<T::Value>
+            where
+                T: serde::de::DeserializeSeed<'de>,
+            {
+                let mut deserializer = serde_json::Deserializer::from_str(input);
+                seed.deserialize(&mut deserializer)
+                    .map_err(serde::de::Error::custom)
+            }
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        enum Enum {
+            NoData,
+            WithData(u8),
+            WithTuple(u8, u8),
+            WithFields { a: u8, b: u8 },
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Unit;
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct Struct {
+            field: u8,
+        }
+
+        #[test]
+        fn unit_variants_and_structs_stay_bare() {
+            assert_eq!(to_str_with_payload(&Enum::NoData, &Json).unwrap(), "NoData");
+            assert_eq!(to_str_with_payload(&Unit, &Json).unwrap(), "Unit");
+
+            assert_eq!(
+                from_str_with_payload::<Enum, _>("NoData", &Json).unwrap(),
+                Enum::NoData
+            );
+            assert_eq!(from_str_with_payload::<Unit, _>("Unit", &Json).unwrap(), Unit);
+        }
+
+        #[test]
+        fn unit_struct_with_unexpected_payload_errors() {
+            assert!(from_str_with_payload::<Unit, _>("Unit(1)", &Json).is_err());
+        }
+
+        #[test]
+        fn newtype_variants_round_trip() {
+            let encoded = to_str_with_payload(&Enum::WithData(42), &Json).unwrap();
+            assert_eq!(encoded, "WithData(42)");
+            assert_eq!(
+                from_str_with_payload::<Enum, _>(&encoded, &Json).unwrap(),
+                Enum::WithData(42)
+            );
+        }
+
+        #[test]
+        fn tuple_variants_round_trip() {
+            let encoded = to_str_with_payload(&Enum::WithTuple(1, 2), &Json).unwrap();
+            assert_eq!(
+                from_str_with_payload::<Enum, _>(&encoded, &Json).unwrap(),
+                Enum::WithTuple(1, 2)
+            );
+        }
+
+        #[test]
+        fn struct_variants_round_trip() {
+            let encoded = to_str_with_payload(&Enum::WithFields { a: 1, b: 2 }, &Json).unwrap();
+            assert_eq!(
+                from_str_with_payload::<Enum, _>(&encoded, &Json).unwrap(),
+                Enum::WithFields { a: 1, b: 2 }
+            );
+        }
+
+        #[test]
+        fn structs_round_trip() {
+            let encoded = to_str_with_payload(&Struct { field: 7 }, &Json).unwrap();
+            assert_eq!(encoded, "Struct(field=7)");
+            assert_eq!(
+                from_str_with_payload::<Struct, _>(&encoded, &Json).unwrap(),
+                Struct { field: 7 }
+            );
+        }
+
+        #[test]
+        fn unknown_variant_errors() {
+            assert!(from_str_with_payload::<Enum, _>("Missing", &Json).is_err());
+        }
+
+        #[test]
+        fn trailing_characters_after_payload_error() {
+            assert!(from_str_with_payload::<Enum, _>("WithData(1)garbage", &Json).is_err());
+        }
+
+        #[derive(Debug, PartialEq, Serialize, Deserialize)]
+        struct TwoFields {
+            a: Vec<u8>,
+            b: u8,
+        }
+
+        #[test]
+        fn field_containing_a_reserved_delimiter_errors_instead_of_corrupting() {
+            assert!(to_str_with_payload(&TwoFields { a: vec![1, 2, 3], b: 9 }, &Json).is_err());
+        }
+    }
+
+    mod name {
+        use super::*;
+
+        #[derive(Serialize)]
+        enum Kind {
+            Created,
+            Updated(u8),
+        }
+
+        #[derive(Serialize)]
+        struct Unit;
+
+        #[derive(Serialize)]
+        struct Event {
+            kind: Kind,
+            id: u64,
+        }
+
+        #[derive(Serialize)]
+        struct Wrapper {
+            inner: Wrapped,
+        }
+
+        #[derive(Serialize)]
+        struct Wrapped {
+            kind: Kind,
+        }
+
+        #[test]
+        fn top_level_enum_and_struct() {
+            assert_eq!(name_of(&Kind::Created).unwrap(), "Created");
+            assert_eq!(name_of(&Unit).unwrap(), "Unit");
+        }
+
+        #[test]
+        fn descends_into_struct_field() {
+            assert_eq!(
+                name_of(&Event {
+                    kind: Kind::Updated(1),
+                    id: 7,
+                })
+                .unwrap(),
+                "Updated"
+            );
+        }
+
+        #[test]
+        fn descends_through_nested_structs() {
+            assert_eq!(
+                name_of(&Wrapper {
+                    inner: Wrapped { kind: Kind::Created },
+                })
+                .unwrap(),
+                "Created"
+            );
+        }
+
+        #[test]
+        fn descends_into_map_value() {
+            let mut map = std::collections::BTreeMap::new();
+            map.insert("kind", Kind::Created);
+            assert_eq!(name_of(&map).unwrap(), "Created");
+        }
+
+        #[test]
+        fn primitive_is_inapplicable() {
+            assert!(name_of(&42u8).is_err());
+        }
+
+        #[test]
+        fn struct_with_no_nameable_field_is_inapplicable() {
+            #[derive(Serialize)]
+            struct OnlyPrimitives {
+                a: u8,
+                b: u8,
+            }
+
+            assert!(name_of(&OnlyPrimitives { a: 1, b: 2 }).is_err());
+        }
+    }
 }
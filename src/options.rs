@@ -0,0 +1,82 @@
+use crate::error::ErrorCode;
+use crate::{de, Error, Result};
+use serde::Deserialize;
+
+/// A builder for a configurable counterpart of [`crate::from_str`], which
+/// itself always matches variant/struct names strictly
+///
+/// By default `Options` matches the strict behavior of [`crate::from_str`];
+/// enable the toggles below to accept user-supplied identifiers without
+/// hand-normalizing them first
+///
+/// ```rust
+///use serde::Deserialize;
+///use serde_typename::Options;
+///
+///#[derive(Debug, PartialEq, Deserialize)]
+///enum Enum {
+///    Foo,
+///}
+///
+///let options = Options::new().case_insensitive(true).trim_whitespace(true);
+///assert_eq!(options.from_str::<Enum>("  foo  ").unwrap(), Enum::Foo);
+/// ```
+#[derive(Clone, Copy, Debug, Default)]
+pub struct Options {
+    case_insensitive: bool,
+    trim_whitespace: bool,
+    allow_trailing: bool,
+}
+
+impl Options {
+    /// Create an `Options` builder with the strict defaults
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Match variant/struct names ignoring ASCII case
+    pub fn case_insensitive(mut self, case_insensitive: bool) -> Self {
+        self.case_insensitive = case_insensitive;
+        self
+    }
+
+    /// Strip leading and trailing whitespace from the input before matching
+    pub fn trim_whitespace(mut self, trim_whitespace: bool) -> Self {
+        self.trim_whitespace = trim_whitespace;
+        self
+    }
+
+    /// Don't error out when characters remain after the recognized name
+    pub fn allow_trailing(mut self, allow_trailing: bool) -> Self {
+        self.allow_trailing = allow_trailing;
+        self
+    }
+
+    /// Convert a variant name back into an enum or struct according to these options
+    pub fn from_str<'a, D>(&self, input: &'a str) -> Result<D>
+    where
+        D: Deserialize<'a>,
+    {
+        let (input, leading_trimmed) = if self.trim_whitespace {
+            let trimmed = input.trim();
+            (trimmed, input.len() - input.trim_start().len())
+        } else {
+            (input, 0)
+        };
+
+        let original_len = input.len();
+        let mut deserializer = de::Deserializer::with_options(input, self.case_insensitive);
+        let variant = D::deserialize(&mut deserializer)?;
+
+        if self.allow_trailing || deserializer.input.is_empty() {
+            Ok(variant)
+        } else {
+            let at = leading_trimmed + (original_len - deserializer.input.len());
+            let rest = deserializer.input.to_string();
+            Err(Error::deserialization_at(
+                ErrorCode::TrailingCharacters { at, rest },
+                at,
+            ))
+        }
+    }
+}
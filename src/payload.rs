@@ -0,0 +1,19 @@
+use crate::Result;
+use serde::de::DeserializeSeed;
+
+/// A pluggable format that encodes/decodes the payload carried by
+/// data-bearing variants and structs when round-tripping through
+/// [`crate::to_str_with_payload`] / [`crate::from_str_with_payload`]
+///
+/// `serde_typename` normally discards a variant's data when resolving its
+/// name; a `PayloadFormat` lets the name act as a tag while delegating the
+/// payload itself to any serde-compatible format (e.g. a thin JSON adapter)
+pub trait PayloadFormat {
+    /// Encode `value` into its textual representation
+    fn encode(&self, value: &dyn erased_serde::Serialize) -> Result<String>;
+
+    /// Decode `input` by feeding it to `seed`
+    fn decode<'de, T>(&self, input: &'de str, seed: T) -> Result<T::Value>
+    where
+        T: DeserializeSeed<'de>;
+}
@@ -0,0 +1,820 @@
+use crate::error::{Error, ErrorCode};
+use crate::payload::PayloadFormat;
+use serde::ser::{self, Impossible, Serialize};
+use std::io;
+
+/// A serializer that resolves the serde name of a value
+///
+/// All data held by the value is discarded, only the
+/// variant/struct name is kept and returned as the result
+pub struct Serializer {}
+
+macro_rules! unsupported {
+    ($name:ident, $ty:ty) => {
+        fn $name(self, _v: $ty) -> Result<Self::Ok, Self::Error> {
+            Err(Error::serialization(ErrorCode::UnsupportedOperation(
+                stringify!($name).to_string(),
+            )))
+        }
+    };
+}
+
+impl ser::Serializer for &mut Serializer {
+    type Ok = &'static str;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = SerializeTupleStruct;
+    type SerializeTupleVariant = SerializeTupleVariant;
+    type SerializeMap = Impossible<Self::Ok, Self::Error>;
+    type SerializeStruct = SerializeStruct;
+    type SerializeStructVariant = SerializeStructVariant;
+
+    unsupported!(serialize_bool, bool);
+    unsupported!(serialize_i8, i8);
+    unsupported!(serialize_i16, i16);
+    unsupported!(serialize_i32, i32);
+    unsupported!(serialize_i64, i64);
+    unsupported!(serialize_u8, u8);
+    unsupported!(serialize_u16, u16);
+    unsupported!(serialize_u32, u32);
+    unsupported!(serialize_u64, u64);
+    unsupported!(serialize_f32, f32);
+    unsupported!(serialize_f64, f64);
+    unsupported!(serialize_char, char);
+    unsupported!(serialize_str, &str);
+    unsupported!(serialize_bytes, &[u8]);
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Err(Error::serialization(ErrorCode::UnsupportedOperation(
+            "none".to_string(),
+        )))
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::serialization(ErrorCode::UnsupportedOperation(
+            "some".to_string(),
+        )))
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Err(Error::serialization(ErrorCode::UnsupportedOperation(
+            "unit".to_string(),
+        )))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(variant)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(name)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(variant)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(Error::serialization(ErrorCode::UnsupportedOperation(
+            "seq".to_string(),
+        )))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(Error::serialization(ErrorCode::UnsupportedOperation(
+            "tuple".to_string(),
+        )))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(SerializeTupleStruct { name })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(SerializeTupleVariant { name: variant })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(Error::serialization(ErrorCode::UnsupportedOperation(
+            "map".to_string(),
+        )))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(SerializeStruct { name })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(SerializeStructVariant { name: variant })
+    }
+}
+
+/// Serializes a tuple struct by discarding its fields and keeping the name
+pub struct SerializeTupleStruct {
+    name: &'static str,
+}
+
+impl ser::SerializeTupleStruct for SerializeTupleStruct {
+    type Ok = &'static str;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.name)
+    }
+}
+
+/// Serializes a tuple variant by discarding its fields and keeping the name
+pub struct SerializeTupleVariant {
+    name: &'static str,
+}
+
+impl ser::SerializeTupleVariant for SerializeTupleVariant {
+    type Ok = &'static str;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.name)
+    }
+}
+
+/// Serializes a struct by discarding its fields and keeping the name
+pub struct SerializeStruct {
+    name: &'static str,
+}
+
+impl ser::SerializeStruct for SerializeStruct {
+    type Ok = &'static str;
+    type Error = Error;
+
+    fn serialize_field<T>(
+        &mut self,
+        _key: &'static str,
+        _value: &T,
+    ) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.name)
+    }
+}
+
+/// Serializes a struct variant by discarding its fields and keeping the name
+pub struct SerializeStructVariant {
+    name: &'static str,
+}
+
+impl ser::SerializeStructVariant for SerializeStructVariant {
+    type Ok = &'static str;
+    type Error = Error;
+
+    fn serialize_field<T>(
+        &mut self,
+        _key: &'static str,
+        _value: &T,
+    ) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(self.name)
+    }
+}
+
+pub(crate) fn write_name<W: io::Write>(writer: &mut W, name: &str) -> Result<(), Error> {
+    writer
+        .write_all(name.as_bytes())
+        .map_err(|err| Error::serialization(ErrorCode::Io(err.to_string())))
+}
+
+/// A serializer that encodes a value as `"Name(payload)"`, delegating the
+/// payload to a [`PayloadFormat`]
+///
+/// Unit variants/structs keep emitting the bare name with no parentheses,
+/// preserving [`crate::to_str`]'s output for data-free types
+pub struct PayloadSerializer<'a, F> {
+    inner: &'a F,
+}
+
+impl<'a, F> PayloadSerializer<'a, F>
+where
+    F: PayloadFormat,
+{
+    pub(crate) fn new(inner: &'a F) -> Self {
+        PayloadSerializer { inner }
+    }
+}
+
+impl<'a, F> ser::Serializer for PayloadSerializer<'a, F>
+where
+    F: PayloadFormat,
+{
+    type Ok = String;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = SerializeTuplePayload<'a, F>;
+    type SerializeTupleVariant = SerializeTuplePayload<'a, F>;
+    type SerializeMap = Impossible<Self::Ok, Self::Error>;
+    type SerializeStruct = SerializeStructPayload<'a, F>;
+    type SerializeStructVariant = SerializeStructPayload<'a, F>;
+
+    unsupported!(serialize_bool, bool);
+    unsupported!(serialize_i8, i8);
+    unsupported!(serialize_i16, i16);
+    unsupported!(serialize_i32, i32);
+    unsupported!(serialize_i64, i64);
+    unsupported!(serialize_u8, u8);
+    unsupported!(serialize_u16, u16);
+    unsupported!(serialize_u32, u32);
+    unsupported!(serialize_u64, u64);
+    unsupported!(serialize_f32, f32);
+    unsupported!(serialize_f64, f64);
+    unsupported!(serialize_char, char);
+    unsupported!(serialize_str, &str);
+    unsupported!(serialize_bytes, &[u8]);
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Err(Error::serialization(ErrorCode::UnsupportedOperation(
+            "none".to_string(),
+        )))
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::serialization(ErrorCode::UnsupportedOperation(
+            "some".to_string(),
+        )))
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Err(Error::serialization(ErrorCode::UnsupportedOperation(
+            "unit".to_string(),
+        )))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(name.to_string())
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(variant.to_string())
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(format!("{}({})", name, self.inner.encode(&value)?))
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(format!("{}({})", variant, self.inner.encode(&value)?))
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(Error::serialization(ErrorCode::UnsupportedOperation(
+            "seq".to_string(),
+        )))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(Error::serialization(ErrorCode::UnsupportedOperation(
+            "tuple".to_string(),
+        )))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(SerializeTuplePayload {
+            inner: self.inner,
+            name,
+            fields: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(SerializeTuplePayload {
+            inner: self.inner,
+            name: variant,
+            fields: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Err(Error::serialization(ErrorCode::UnsupportedOperation(
+            "map".to_string(),
+        )))
+    }
+
+    fn serialize_struct(
+        self,
+        name: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(SerializeStructPayload {
+            inner: self.inner,
+            name,
+            fields: Vec::with_capacity(len),
+        })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(SerializeStructPayload {
+            inner: self.inner,
+            name: variant,
+            fields: Vec::with_capacity(len),
+        })
+    }
+}
+
+/// Rejects an encoded field that contains one of `forbidden`'s characters,
+/// since those are reserved to delimit fields in a tuple/struct payload and
+/// aren't escaped; an inner format whose output can embed them (rather than
+/// erroring here) would otherwise corrupt the round trip through
+/// [`crate::from_str_with_payload`]
+fn reject_delimiters(encoded: &str, forbidden: &[char]) -> Result<(), Error> {
+    if encoded.contains(forbidden) {
+        Err(Error::serialization(ErrorCode::UnsupportedOperation(
+            format!(
+                "encoded field {:?} contains a reserved payload delimiter ({:?})",
+                encoded, forbidden
+            ),
+        )))
+    } else {
+        Ok(())
+    }
+}
+
+/// Accumulates a tuple struct/variant's encoded fields until `end()` joins
+/// them with `,`
+///
+/// A field whose encoded form contains `,` is rejected by [`reject_delimiters`]
+/// instead of silently corrupting the payload
+pub struct SerializeTuplePayload<'a, F> {
+    inner: &'a F,
+    name: &'static str,
+    fields: Vec<String>,
+}
+
+impl<'a, F> ser::SerializeTupleStruct for SerializeTuplePayload<'a, F>
+where
+    F: PayloadFormat,
+{
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let encoded = self.inner.encode(&value)?;
+        reject_delimiters(&encoded, &[','])?;
+        self.fields.push(encoded);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(format!("{}({})", self.name, self.fields.join(",")))
+    }
+}
+
+impl<'a, F> ser::SerializeTupleVariant for SerializeTuplePayload<'a, F>
+where
+    F: PayloadFormat,
+{
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let encoded = self.inner.encode(&value)?;
+        reject_delimiters(&encoded, &[','])?;
+        self.fields.push(encoded);
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        Ok(format!("{}({})", self.name, self.fields.join(",")))
+    }
+}
+
+/// Accumulates a struct/struct variant's encoded fields until `end()` joins
+/// them as `key=value` pairs separated by `,`
+///
+/// A field whose encoded form contains `,` or `=` is rejected by
+/// [`reject_delimiters`] instead of silently corrupting the payload
+pub struct SerializeStructPayload<'a, F> {
+    inner: &'a F,
+    name: &'static str,
+    fields: Vec<(&'static str, String)>,
+}
+
+impl<'a, F> ser::SerializeStruct for SerializeStructPayload<'a, F>
+where
+    F: PayloadFormat,
+{
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let encoded = self.inner.encode(&value)?;
+        reject_delimiters(&encoded, &[',', '='])?;
+        self.fields.push((key, encoded));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        let payload = self
+            .fields
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(",");
+        Ok(format!("{}({})", self.name, payload))
+    }
+}
+
+impl<'a, F> ser::SerializeStructVariant for SerializeStructPayload<'a, F>
+where
+    F: PayloadFormat,
+{
+    type Ok = String;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        let encoded = self.inner.encode(&value)?;
+        reject_delimiters(&encoded, &[',', '='])?;
+        self.fields.push((key, encoded));
+        Ok(())
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        let payload = self
+            .fields
+            .iter()
+            .map(|(key, value)| format!("{}={}", key, value))
+            .collect::<Vec<_>>()
+            .join(",");
+        Ok(format!("{}({})", self.name, payload))
+    }
+}
+
+macro_rules! inapplicable {
+    ($name:ident, $ty:ty) => {
+        fn $name(self, _v: $ty) -> Result<Self::Ok, Self::Error> {
+            Err(Error::serialization(ErrorCode::Inapplicable))
+        }
+    };
+}
+
+/// A serializer that resolves the serde name of a value, descending into the
+/// fields of a struct with named fields or the values of a map instead of
+/// returning that struct's own name
+///
+/// Following elfo's `NameExtractor` design, every method that could not
+/// possibly see a name returns an [`ErrorCode::Inapplicable`] error rather
+/// than [`ErrorCode::UnsupportedOperation`], which is what lets
+/// [`StructNameExtractor`]/[`MapNameExtractor`] tell "this field had no
+/// name to give" apart from a genuine failure while trying each field in turn
+pub struct NameExtractor {}
+
+impl ser::Serializer for &mut NameExtractor {
+    type Ok = &'static str;
+    type Error = Error;
+
+    type SerializeSeq = Impossible<Self::Ok, Self::Error>;
+    type SerializeTuple = Impossible<Self::Ok, Self::Error>;
+    type SerializeTupleStruct = SerializeTupleStruct;
+    type SerializeTupleVariant = SerializeTupleVariant;
+    type SerializeMap = MapNameExtractor;
+    type SerializeStruct = StructNameExtractor;
+    type SerializeStructVariant = StructNameExtractor;
+
+    inapplicable!(serialize_bool, bool);
+    inapplicable!(serialize_i8, i8);
+    inapplicable!(serialize_i16, i16);
+    inapplicable!(serialize_i32, i32);
+    inapplicable!(serialize_i64, i64);
+    inapplicable!(serialize_u8, u8);
+    inapplicable!(serialize_u16, u16);
+    inapplicable!(serialize_u32, u32);
+    inapplicable!(serialize_u64, u64);
+    inapplicable!(serialize_f32, f32);
+    inapplicable!(serialize_f64, f64);
+    inapplicable!(serialize_char, char);
+    inapplicable!(serialize_str, &str);
+    inapplicable!(serialize_bytes, &[u8]);
+
+    fn serialize_none(self) -> Result<Self::Ok, Self::Error> {
+        Err(Error::serialization(ErrorCode::Inapplicable))
+    }
+
+    fn serialize_some<T>(self, _value: &T) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Err(Error::serialization(ErrorCode::Inapplicable))
+    }
+
+    fn serialize_unit(self) -> Result<Self::Ok, Self::Error> {
+        Err(Error::serialization(ErrorCode::Inapplicable))
+    }
+
+    fn serialize_unit_struct(self, name: &'static str) -> Result<Self::Ok, Self::Error> {
+        Ok(name)
+    }
+
+    fn serialize_unit_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+    ) -> Result<Self::Ok, Self::Error> {
+        Ok(variant)
+    }
+
+    fn serialize_newtype_struct<T>(
+        self,
+        name: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(name)
+    }
+
+    fn serialize_newtype_variant<T>(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _value: &T,
+    ) -> Result<Self::Ok, Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(variant)
+    }
+
+    fn serialize_seq(self, _len: Option<usize>) -> Result<Self::SerializeSeq, Self::Error> {
+        Err(Error::serialization(ErrorCode::Inapplicable))
+    }
+
+    fn serialize_tuple(self, _len: usize) -> Result<Self::SerializeTuple, Self::Error> {
+        Err(Error::serialization(ErrorCode::Inapplicable))
+    }
+
+    fn serialize_tuple_struct(
+        self,
+        name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleStruct, Self::Error> {
+        Ok(SerializeTupleStruct { name })
+    }
+
+    fn serialize_tuple_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeTupleVariant, Self::Error> {
+        Ok(SerializeTupleVariant { name: variant })
+    }
+
+    fn serialize_map(self, _len: Option<usize>) -> Result<Self::SerializeMap, Self::Error> {
+        Ok(MapNameExtractor { name: None })
+    }
+
+    fn serialize_struct(
+        self,
+        _name: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStruct, Self::Error> {
+        Ok(StructNameExtractor { name: None })
+    }
+
+    fn serialize_struct_variant(
+        self,
+        _name: &'static str,
+        _variant_index: u32,
+        variant: &'static str,
+        _len: usize,
+    ) -> Result<Self::SerializeStructVariant, Self::Error> {
+        Ok(StructNameExtractor {
+            name: Some(variant),
+        })
+    }
+}
+
+/// Tries serializing `value` in search of a name, returning `Ok(())` both when
+/// `name` was already resolved by an earlier field and when `value` itself has
+/// no name ([`ErrorCode::Inapplicable`])
+fn try_field<T>(name: &mut Option<&'static str>, value: &T) -> Result<(), Error>
+where
+    T: ?Sized + Serialize,
+{
+    if name.is_some() {
+        return Ok(());
+    }
+
+    match value.serialize(&mut NameExtractor {}) {
+        Ok(resolved) => {
+            *name = Some(resolved);
+            Ok(())
+        }
+        Err(err) if err.is_inapplicable() => Ok(()),
+        Err(err) => Err(err),
+    }
+}
+
+/// Tries each struct/struct variant field in turn, keeping the first one
+/// that resolves to a name and ignoring the rest
+///
+/// A field that fails for a real reason (not just "no name here") is
+/// propagated immediately instead of being swallowed like an inapplicable one.
+/// A struct variant starts out resolved to its variant name, so its fields
+/// are not searched
+pub struct StructNameExtractor {
+    name: Option<&'static str>,
+}
+
+impl ser::SerializeStruct for StructNameExtractor {
+    type Ok = &'static str;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        try_field(&mut self.name, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.name
+            .ok_or_else(|| Error::serialization(ErrorCode::Inapplicable))
+    }
+}
+
+impl ser::SerializeStructVariant for StructNameExtractor {
+    type Ok = &'static str;
+    type Error = Error;
+
+    fn serialize_field<T>(&mut self, _key: &'static str, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        try_field(&mut self.name, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.name
+            .ok_or_else(|| Error::serialization(ErrorCode::Inapplicable))
+    }
+}
+
+/// Tries each map entry's value in turn, the same way [`StructNameExtractor`]
+/// tries each struct field; keys are never inspected
+pub struct MapNameExtractor {
+    name: Option<&'static str>,
+}
+
+impl ser::SerializeMap for MapNameExtractor {
+    type Ok = &'static str;
+    type Error = Error;
+
+    fn serialize_key<T>(&mut self, _key: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        Ok(())
+    }
+
+    fn serialize_value<T>(&mut self, value: &T) -> Result<(), Self::Error>
+    where
+        T: ?Sized + Serialize,
+    {
+        try_field(&mut self.name, value)
+    }
+
+    fn end(self) -> Result<Self::Ok, Self::Error> {
+        self.name
+            .ok_or_else(|| Error::serialization(ErrorCode::Inapplicable))
+    }
+}